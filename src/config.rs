@@ -0,0 +1,195 @@
+use std::env;
+
+/// Symbols and colors used by `print_prompt`, overridable via `GIT_ZPROMPT_*` environment
+/// variables so users on limited terminals or with differing taste don't need to recompile.
+#[derive(Debug)]
+pub struct Config {
+    pub stash_symbol: String,
+    pub stash_color: String,
+
+    pub merge_marker: String,
+    pub merge_color: String,
+    pub revert_marker: String,
+    pub revert_color: String,
+    pub cherry_pick_marker: String,
+    pub cherry_pick_color: String,
+    pub bisect_marker: String,
+    pub bisect_color: String,
+    pub rebase_marker: String,
+    pub rebase_color: String,
+    pub apply_mailbox_marker: String,
+    pub apply_mailbox_color: String,
+
+    pub staged_symbol: String,
+    pub staged_color: String,
+    pub modified_symbol: String,
+    pub modified_color: String,
+    pub untracked_symbol: String,
+    pub untracked_color: String,
+    pub renamed_symbol: String,
+    pub renamed_color: String,
+    pub deleted_symbol: String,
+    pub deleted_color: String,
+    pub conflict_symbol: String,
+    pub conflict_color: String,
+
+    pub branch_color: String,
+    pub remote_branch_color: String,
+    pub tag_symbol: String,
+    pub tag_color: String,
+    pub commit_color: String,
+    pub upstream_color: String,
+    pub empty_repo_color: String,
+    pub unknown_symbol: String,
+    pub unknown_color: String,
+
+    pub ahead_symbol: String,
+    pub behind_symbol: String,
+    pub diverged_symbol: String,
+
+    /// Skip the full work-tree walk when a cheap index/mtime check shows no *tracked* file has
+    /// changed. That check has a blind spot — it can't see brand-new untracked files, since
+    /// they have no index entry to compare mtimes against — so this defaults to off; set
+    /// `GIT_ZPROMPT_FAST_STATUS=1` to opt in on repos where that trade-off is acceptable.
+    pub fast_status: bool,
+    /// Above this many index entries, disable untracked-file enumeration during the full
+    /// work-tree walk, since that's the part that scales with repo size rather than change size.
+    pub untracked_limit: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            stash_symbol: "🚧".to_string(),
+            stash_color: "default".to_string(),
+
+            merge_marker: "🔀 MERGING".to_string(),
+            merge_color: "magenta".to_string(),
+            revert_marker: "⏪ REVERT".to_string(),
+            revert_color: "magenta".to_string(),
+            cherry_pick_marker: "🍒 CHERRY-PICK".to_string(),
+            cherry_pick_color: "magenta".to_string(),
+            bisect_marker: "🔍 BISECT".to_string(),
+            bisect_color: "yellow".to_string(),
+            rebase_marker: "♻ REBASE".to_string(),
+            rebase_color: "magenta".to_string(),
+            apply_mailbox_marker: "✉ AM".to_string(),
+            apply_mailbox_color: "magenta".to_string(),
+
+            staged_symbol: "🗸".to_string(),
+            staged_color: "green".to_string(),
+            modified_symbol: "•".to_string(),
+            modified_color: "yellow".to_string(),
+            untracked_symbol: "?".to_string(),
+            untracked_color: "yellow".to_string(),
+            renamed_symbol: "»".to_string(),
+            renamed_color: "blue".to_string(),
+            deleted_symbol: "✘".to_string(),
+            deleted_color: "red".to_string(),
+            conflict_symbol: "✘".to_string(),
+            conflict_color: "red".to_string(),
+
+            branch_color: "green".to_string(),
+            remote_branch_color: "red".to_string(),
+            tag_symbol: "🔖".to_string(),
+            tag_color: "blue".to_string(),
+            commit_color: "blue".to_string(),
+            upstream_color: "red".to_string(),
+            empty_repo_color: "red".to_string(),
+            unknown_symbol: "?".to_string(),
+            unknown_color: "red".to_string(),
+
+            ahead_symbol: "↑".to_string(),
+            behind_symbol: "↓".to_string(),
+            diverged_symbol: "⇕".to_string(),
+
+            fast_status: false,
+            untracked_limit: 4000,
+        }
+    }
+}
+
+impl Config {
+    /// Load overrides from `GIT_ZPROMPT_*` environment variables, falling back to the defaults
+    /// above for anything not set.
+    pub fn from_env() -> Self {
+        let default = Config::default();
+        Config {
+            stash_symbol: env_or("GIT_ZPROMPT_STASH_SYMBOL", default.stash_symbol),
+            stash_color: env_or("GIT_ZPROMPT_STASH_COLOR", default.stash_color),
+
+            merge_marker: env_or("GIT_ZPROMPT_MERGE_MARKER", default.merge_marker),
+            merge_color: env_or("GIT_ZPROMPT_MERGE_COLOR", default.merge_color),
+            revert_marker: env_or("GIT_ZPROMPT_REVERT_MARKER", default.revert_marker),
+            revert_color: env_or("GIT_ZPROMPT_REVERT_COLOR", default.revert_color),
+            cherry_pick_marker: env_or(
+                "GIT_ZPROMPT_CHERRY_PICK_MARKER",
+                default.cherry_pick_marker,
+            ),
+            cherry_pick_color: env_or("GIT_ZPROMPT_CHERRY_PICK_COLOR", default.cherry_pick_color),
+            bisect_marker: env_or("GIT_ZPROMPT_BISECT_MARKER", default.bisect_marker),
+            bisect_color: env_or("GIT_ZPROMPT_BISECT_COLOR", default.bisect_color),
+            rebase_marker: env_or("GIT_ZPROMPT_REBASE_MARKER", default.rebase_marker),
+            rebase_color: env_or("GIT_ZPROMPT_REBASE_COLOR", default.rebase_color),
+            apply_mailbox_marker: env_or(
+                "GIT_ZPROMPT_APPLY_MAILBOX_MARKER",
+                default.apply_mailbox_marker,
+            ),
+            apply_mailbox_color: env_or(
+                "GIT_ZPROMPT_APPLY_MAILBOX_COLOR",
+                default.apply_mailbox_color,
+            ),
+
+            staged_symbol: env_or("GIT_ZPROMPT_STAGED_SYMBOL", default.staged_symbol),
+            staged_color: env_or("GIT_ZPROMPT_STAGED_COLOR", default.staged_color),
+            modified_symbol: env_or("GIT_ZPROMPT_MODIFIED_SYMBOL", default.modified_symbol),
+            modified_color: env_or("GIT_ZPROMPT_MODIFIED_COLOR", default.modified_color),
+            untracked_symbol: env_or("GIT_ZPROMPT_UNTRACKED_SYMBOL", default.untracked_symbol),
+            untracked_color: env_or("GIT_ZPROMPT_UNTRACKED_COLOR", default.untracked_color),
+            renamed_symbol: env_or("GIT_ZPROMPT_RENAMED_SYMBOL", default.renamed_symbol),
+            renamed_color: env_or("GIT_ZPROMPT_RENAMED_COLOR", default.renamed_color),
+            deleted_symbol: env_or("GIT_ZPROMPT_DELETED_SYMBOL", default.deleted_symbol),
+            deleted_color: env_or("GIT_ZPROMPT_DELETED_COLOR", default.deleted_color),
+            conflict_symbol: env_or("GIT_ZPROMPT_CONFLICT_SYMBOL", default.conflict_symbol),
+            conflict_color: env_or("GIT_ZPROMPT_CONFLICT_COLOR", default.conflict_color),
+
+            branch_color: env_or("GIT_ZPROMPT_BRANCH_COLOR", default.branch_color),
+            remote_branch_color: env_or(
+                "GIT_ZPROMPT_REMOTE_BRANCH_COLOR",
+                default.remote_branch_color,
+            ),
+            tag_symbol: env_or("GIT_ZPROMPT_TAG_SYMBOL", default.tag_symbol),
+            tag_color: env_or("GIT_ZPROMPT_TAG_COLOR", default.tag_color),
+            commit_color: env_or("GIT_ZPROMPT_COMMIT_COLOR", default.commit_color),
+            upstream_color: env_or("GIT_ZPROMPT_UPSTREAM_COLOR", default.upstream_color),
+            empty_repo_color: env_or("GIT_ZPROMPT_EMPTY_REPO_COLOR", default.empty_repo_color),
+            unknown_symbol: env_or("GIT_ZPROMPT_UNKNOWN_SYMBOL", default.unknown_symbol),
+            unknown_color: env_or("GIT_ZPROMPT_UNKNOWN_COLOR", default.unknown_color),
+
+            ahead_symbol: env_or("GIT_ZPROMPT_AHEAD_SYMBOL", default.ahead_symbol),
+            behind_symbol: env_or("GIT_ZPROMPT_BEHIND_SYMBOL", default.behind_symbol),
+            diverged_symbol: env_or("GIT_ZPROMPT_DIVERGED_SYMBOL", default.diverged_symbol),
+
+            fast_status: env_bool("GIT_ZPROMPT_FAST_STATUS", default.fast_status),
+            untracked_limit: env_usize("GIT_ZPROMPT_UNTRACKED_LIMIT", default.untracked_limit),
+        }
+    }
+}
+
+fn env_or(key: &str, default: String) -> String {
+    env::var(key).unwrap_or(default)
+}
+
+fn env_bool(key: &str, default: bool) -> bool {
+    match env::var(key) {
+        Ok(value) => !matches!(value.as_str(), "0" | "false"),
+        Err(_) => default,
+    }
+}
+
+fn env_usize(key: &str, default: usize) -> usize {
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
@@ -5,11 +5,16 @@ use std::{
     io::{stdout, Write},
 };
 
-use git2::{BranchType, Oid, Repository, Status, StatusOptions, StatusShow};
+use git2::{
+    BranchType, DescribeFormatOptions, DescribeOptions, Oid, Repository, RepositoryState, Status,
+    StatusOptions, StatusShow,
+};
 
 use crate::color::{bold_str, colored_str};
+use crate::config::Config;
 
 mod color;
+mod config;
 
 #[derive(Debug)]
 pub enum HeadInfo {
@@ -24,8 +29,12 @@ pub enum HeadInfo {
     RemoteBranch { name: String },
     /// Checking out a tag
     Tag { name: String },
-    /// None of the above, fallback to commit hash
-    Commit { hash: String },
+    /// None of the above (detached HEAD with no tag/remote match), described via `git describe`
+    /// when possible (e.g. `v1.4.2-7-gdeadbeef12345`), falling back to the short hash otherwise
+    Commit { description: String },
+    /// Head info couldn't be determined (shallow clone, corrupt refs, etc.) — degrade instead of
+    /// crashing the shell
+    Unknown,
 }
 
 #[derive(Debug, Default)]
@@ -39,6 +48,44 @@ pub struct StagingStat {
     pub modified: usize,
     pub staged: usize,
     pub conflict: usize,
+    pub untracked: usize,
+    pub renamed: usize,
+    pub deleted: usize,
+}
+
+/// In-progress repository operation, as reported by `Repository::state()`.
+#[derive(Debug)]
+pub enum RepoState {
+    Merge,
+    Revert,
+    RevertSequence,
+    CherryPick,
+    CherryPickSequence,
+    Bisect,
+    Rebase,
+    RebaseInteractive,
+    RebaseMerge,
+    ApplyMailbox,
+    ApplyMailboxOrRebase,
+}
+
+impl RepoState {
+    fn from_git2(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(RepoState::Merge),
+            RepositoryState::Revert => Some(RepoState::Revert),
+            RepositoryState::RevertSequence => Some(RepoState::RevertSequence),
+            RepositoryState::CherryPick => Some(RepoState::CherryPick),
+            RepositoryState::CherryPickSequence => Some(RepoState::CherryPickSequence),
+            RepositoryState::Bisect => Some(RepoState::Bisect),
+            RepositoryState::Rebase => Some(RepoState::Rebase),
+            RepositoryState::RebaseInteractive => Some(RepoState::RebaseInteractive),
+            RepositoryState::RebaseMerge => Some(RepoState::RebaseMerge),
+            RepositoryState::ApplyMailbox => Some(RepoState::ApplyMailbox),
+            RepositoryState::ApplyMailboxOrRebase => Some(RepoState::ApplyMailboxOrRebase),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -47,106 +94,231 @@ pub struct PromptData {
     pub commit_stat: CommitStat,
     pub staging_stat: StagingStat,
     pub stash: usize,
-    // TODO: Repo status: rebasing, cherry-picking, bisect, etc.
-}
-
-fn quit() -> ! {
-    std::process::exit(1);
+    pub repo_state: Option<RepoState>,
 }
 
-fn find_repo_using_current_dir() -> Repository {
-    Repository::discover(&current_dir().unwrap()).unwrap_or_else(|_| quit())
+fn find_repo_using_current_dir() -> Option<Repository> {
+    let dir = current_dir().ok()?;
+    Repository::discover(&dir).ok()
 }
 
 fn find_tag(repo: &Repository, head: Oid) -> Option<String> {
     let mut tag = None;
-    repo.tag_foreach(|oid, name| {
+    let found = repo.tag_foreach(|oid, name| {
         if oid == head {
-            tag = Some(
-                repo.find_reference(std::str::from_utf8(name).unwrap())
-                    .unwrap()
+            tag = (|| {
+                repo.find_reference(std::str::from_utf8(name).ok()?)
+                    .ok()?
                     .shorthand()
-                    .unwrap()
-                    .to_string(),
-            );
+                    .map(|s| s.to_string())
+            })();
         }
         true
-    })
-    .unwrap();
+    });
+    found.ok()?;
     tag
 }
 
 fn prepare_head_info(repo: &Repository) -> HeadInfo {
-    let repo_head = repo.head().unwrap();
-    let head_name = repo_head.shorthand().unwrap().to_string();
-    if repo_head.is_branch() {
-        let branch = repo.find_branch(&head_name, BranchType::Local).unwrap();
+    try_prepare_head_info(repo).unwrap_or(HeadInfo::Unknown)
+}
+
+fn try_prepare_head_info(repo: &Repository) -> Option<HeadInfo> {
+    let repo_head = repo.head().ok()?;
+    let head_name = repo_head.shorthand()?.to_string();
+    Some(if repo_head.is_branch() {
+        let branch = repo.find_branch(&head_name, BranchType::Local).ok()?;
         let upstream = branch
             .upstream()
             .ok()
-            .map(|u| u.name().unwrap().unwrap().to_string());
+            .and_then(|u| u.name().ok().flatten().map(|s| s.to_string()));
         HeadInfo::Branch {
             upstream,
             name: head_name,
         }
-    } else if let Some(tag) = find_tag(repo, repo_head.peel_to_commit().unwrap().id()) {
+    } else if let Some(tag) = repo_head
+        .peel_to_commit()
+        .ok()
+        .and_then(|commit| find_tag(repo, commit.id()))
+    {
         HeadInfo::Tag { name: tag }
     } else if repo_head.is_remote() {
         HeadInfo::RemoteBranch { name: head_name }
     } else {
         HeadInfo::Commit {
-            hash: repo_head.target().unwrap().to_string(),
+            description: describe_head(repo),
         }
-    }
+    })
+}
+
+/// Produce a human-meaningful description of the detached HEAD commit, preferring the nearest
+/// reachable tag (`git describe`) and falling back to the bare short hash when no tag is
+/// reachable, or "unknown" if even that can't be read.
+fn describe_head(repo: &Repository) -> String {
+    repo.describe(
+        DescribeOptions::new()
+            .describe_tags()
+            .show_commit_oid_as_fallback(true),
+    )
+    .and_then(|d| d.format(Some(DescribeFormatOptions::new().abbreviated_size(13))))
+    .ok()
+    .or_else(|| {
+        let hash = repo.head().ok()?.target()?.to_string();
+        Some(hash[0..=12].to_string())
+    })
+    .unwrap_or_else(|| "unknown".to_string())
 }
 
 fn prepare_commit_stat(repo: &Repository, head: &HeadInfo) -> CommitStat {
+    try_prepare_commit_stat(repo, head).unwrap_or_default()
+}
+
+fn try_prepare_commit_stat(repo: &Repository, head: &HeadInfo) -> Option<CommitStat> {
     let mut ahead = 0;
     let mut behind = 0;
     if let HeadInfo::Branch { name, upstream } = head {
         // TODO: Handle empty repo, the branch exists but no commit available
         let local_commit = repo
-            .find_branch(&name, BranchType::Local)
-            .unwrap()
+            .find_branch(name, BranchType::Local)
+            .ok()?
             .get()
             .peel_to_commit()
-            .unwrap();
+            .ok()?;
         match upstream {
             Some(upstream_name) => {
-                let upstream_commit = repo
-                    .find_branch(&upstream_name, BranchType::Remote)
-                    .unwrap()
-                    .get()
-                    .peel_to_commit()
-                    .unwrap();
-                (ahead, behind) = repo
-                    .graph_ahead_behind(local_commit.id(), upstream_commit.id())
-                    .unwrap();
+                // A missing/renamed upstream ref (e.g. its remote-tracking branch was deleted)
+                // just means we can't show ahead/behind, not that the whole prompt should crash
+                if let Some(upstream_commit) = repo
+                    .find_branch(upstream_name, BranchType::Remote)
+                    .ok()
+                    .and_then(|b| b.get().peel_to_commit().ok())
+                {
+                    (ahead, behind) = repo
+                        .graph_ahead_behind(local_commit.id(), upstream_commit.id())
+                        .unwrap_or((0, 0));
+                }
             }
             None => {
                 // No branch to compare, just return total number of commits
-                let mut walk = repo.revwalk().unwrap();
-                walk.push_head().unwrap();
+                let mut walk = repo.revwalk().ok()?;
+                walk.push_head().ok()?;
                 ahead = walk.count();
             }
         }
     }
-    CommitStat { ahead, behind }
+    Some(CommitStat { ahead, behind })
+}
+
+fn prepare_staging_stat(repo: &Repository, config: &Config) -> StagingStat {
+    try_prepare_staging_stat(repo, config).unwrap_or_default()
+}
+
+fn try_prepare_staging_stat(repo: &Repository, config: &Config) -> Option<StagingStat> {
+    if config.fast_status && !workdir_may_have_changed(repo) {
+        // The index's own per-file mtimes all agree with what's on disk, so there's nothing a
+        // full work-tree walk could find beyond what the index (staged/conflicted) already shows
+        // — except a brand-new untracked file, which has no index entry to compare against in
+        // the first place. `workdir_may_have_changed` can't see those, which is why this path
+        // stays opt-in (`fast_status` defaults to off) until it's backed by something like git's
+        // untracked-cache extension.
+        return try_prepare_staging_stat_index_only(repo);
+    }
+    try_prepare_staging_stat_full(repo, config)
+}
+
+/// Cheap pre-check: compares each tracked file's mtime against the one recorded in the index the
+/// last time it was hashed. If they all still match, none of the *tracked* files have changed, so
+/// the expensive recursive walk can be skipped in favor of the index-only path below. Any
+/// inability to compare (unreadable index/file) conservatively reports "may have changed" so we
+/// fall back to the full, correct walk.
+///
+/// Note this only covers tracked files: a newly created, never-tracked file has no index entry
+/// to compare against, so it can't be detected here. See `fast_status` doc comment.
+fn workdir_may_have_changed(repo: &Repository) -> bool {
+    let Some(workdir) = repo.workdir() else {
+        return true;
+    };
+    let index = match repo.index() {
+        Ok(index) => index,
+        Err(_) => return true,
+    };
+    for entry in index.iter() {
+        let Ok(path) = std::str::from_utf8(&entry.path) else {
+            return true;
+        };
+        let metadata = match std::fs::metadata(workdir.join(path)) {
+            Ok(metadata) => metadata,
+            Err(_) => return true,
+        };
+        let actual_mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as u32);
+        if actual_mtime != Some(entry.mtime.seconds() as u32) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Index-only status pass used by the fast path: no work-tree walk, so it can't see untracked,
+/// modified, renamed, or deleted files — only what's already staged or conflicted.
+fn try_prepare_staging_stat_index_only(repo: &Repository) -> Option<StagingStat> {
+    let mut staged = 0;
+    let mut conflict = 0;
+    repo.statuses(Some(
+        StatusOptions::new()
+            .show(StatusShow::Index)
+            .include_unmodified(false),
+    ))
+    .ok()?
+    .iter()
+    .for_each(|status_entry| {
+        let status = status_entry.status();
+        if status.contains(Status::CONFLICTED) {
+            conflict += 1;
+            return;
+        }
+        if status.contains(Status::INDEX_MODIFIED)
+            || status.contains(Status::INDEX_NEW)
+            || status.contains(Status::INDEX_DELETED)
+            || status.contains(Status::INDEX_RENAMED)
+        {
+            staged += 1;
+        }
+    });
+    Some(StagingStat {
+        staged,
+        conflict,
+        ..StagingStat::default()
+    })
 }
 
-fn prepare_staging_stat(repo: &Repository) -> StagingStat {
+fn try_prepare_staging_stat_full(repo: &Repository, config: &Config) -> Option<StagingStat> {
     let mut modified = 0;
     let mut staged = 0;
     let mut conflict = 0;
+    let mut untracked = 0;
+    let mut renamed = 0;
+    let mut deleted = 0;
+    // Untracked enumeration is the part that scales with repo size rather than change size, so
+    // disable it past the configured cap to keep very large repos fast, same idea as git's own
+    // `status.showUntrackedFiles` escape hatch.
+    let include_untracked = repo
+        .index()
+        .map(|index| index.len() <= config.untracked_limit)
+        .unwrap_or(true);
     repo.statuses(Some(
         StatusOptions::new()
             .show(StatusShow::IndexAndWorkdir)
             .include_ignored(false)
-            .include_untracked(true)
+            .include_untracked(include_untracked)
             .include_unmodified(false)
+            .renames_index_to_workdir(true)
             .recurse_untracked_dirs(true),
     ))
-    .unwrap()
+    .ok()?
     .iter()
     .for_each(|status_entry| {
         // Note: A file can be added to staging area and is modified again, don't assume the flag
@@ -163,94 +335,166 @@ fn prepare_staging_stat(repo: &Repository) -> StagingStat {
         if status.contains(Status::INDEX_MODIFIED)
             || status.contains(Status::INDEX_NEW)
             || status.contains(Status::INDEX_DELETED)
+            || status.contains(Status::INDEX_RENAMED)
         {
             staged += 1;
         }
-        if status.contains(Status::WT_MODIFIED)
-            || status.contains(Status::WT_NEW)
-            || status.contains(Status::WT_DELETED)
-        {
+        // The counters below look only at WT_* flags; INDEX_* flags are counted under `staged`
+        // above, so a staged new file (INDEX_NEW) is not also counted as untracked
+        if status.contains(Status::WT_NEW) {
+            untracked += 1;
+        }
+        if status.contains(Status::WT_RENAMED) {
+            renamed += 1;
+        }
+        if status.contains(Status::WT_DELETED) {
+            deleted += 1;
+        }
+        if status.contains(Status::WT_MODIFIED) {
             modified += 1;
         }
     });
-    StagingStat {
+    Some(StagingStat {
         modified,
         staged,
         conflict,
-    }
+        untracked,
+        renamed,
+        deleted,
+    })
 }
 
-fn get_current_branch_in_empty_repo(repo: &Repository) -> String {
+fn get_current_branch_in_empty_repo(repo: &Repository) -> Option<String> {
     let mut path = repo.path().to_path_buf();
     path.push("HEAD");
-    std::fs::read_to_string(path)
-        .unwrap()
-        .trim_start_matches("ref: refs/heads/")
-        .trim_end()
-        .to_string()
+    Some(
+        std::fs::read_to_string(path)
+            .ok()?
+            .trim_start_matches("ref: refs/heads/")
+            .trim_end()
+            .to_string(),
+    )
 }
 
-fn prepare_prompt_data(repo: &mut Repository) -> PromptData {
-    if repo.is_empty().unwrap() {
+fn prepare_prompt_data(repo: &mut Repository, config: &Config) -> PromptData {
+    if repo.is_empty().unwrap_or(false) {
         return PromptData {
             head: HeadInfo::EmptyBranch {
-                name: format!("{}", get_current_branch_in_empty_repo(repo)),
+                name: get_current_branch_in_empty_repo(repo).unwrap_or_default(),
             },
             commit_stat: CommitStat::default(),
             staging_stat: StagingStat::default(),
             stash: 0,
+            repo_state: RepoState::from_git2(repo.state()),
         };
     }
     let head = prepare_head_info(repo);
     let commit_stat = prepare_commit_stat(repo, &head);
-    let staging_stat = prepare_staging_stat(repo);
+    let staging_stat = prepare_staging_stat(repo, config);
     let mut stash = 0;
-    repo.stash_foreach(|_index, _message, _oid| {
+    // A failed stash walk just means we can't report a stash count, not a reason to crash
+    let _ = repo.stash_foreach(|_index, _message, _oid| {
         stash += 1;
         true
-    })
-    .unwrap();
+    });
+    let repo_state = RepoState::from_git2(repo.state());
     PromptData {
         head,
         commit_stat,
         staging_stat,
         stash,
+        repo_state,
     }
 }
 
-fn print_prompt(data: &PromptData) {
+fn print_prompt(data: &PromptData, config: &Config) {
     let stdout = stdout();
     let mut stdout = stdout.lock();
     // Stash info
     if data.stash > 0 {
-        write!(&mut stdout, "🚧{} ", data.stash).unwrap();
+        write!(
+            &mut stdout,
+            "{} ",
+            colored_str(
+                &format!("{}{}", config.stash_symbol, data.stash),
+                &config.stash_color
+            )
+        )
+        .unwrap();
+    }
+    // Repo operation info (rebase, merge, cherry-pick, etc.)
+    if let Some(repo_state) = &data.repo_state {
+        let (marker, color) = match repo_state {
+            RepoState::Merge => (&config.merge_marker, &config.merge_color),
+            RepoState::Revert | RepoState::RevertSequence => {
+                (&config.revert_marker, &config.revert_color)
+            }
+            RepoState::CherryPick | RepoState::CherryPickSequence => {
+                (&config.cherry_pick_marker, &config.cherry_pick_color)
+            }
+            RepoState::Bisect => (&config.bisect_marker, &config.bisect_color),
+            RepoState::Rebase | RepoState::RebaseInteractive | RepoState::RebaseMerge => {
+                (&config.rebase_marker, &config.rebase_color)
+            }
+            RepoState::ApplyMailbox | RepoState::ApplyMailboxOrRebase => {
+                (&config.apply_mailbox_marker, &config.apply_mailbox_color)
+            }
+        };
+        write!(&mut stdout, "{} ", bold_str(&colored_str(marker, color))).unwrap();
     }
     // Staging info (will be mixed in the middle of head info, so we can't print it now)
     let staging_info = if data.staging_stat.modified > 0
         || data.staging_stat.staged > 0
         || data.staging_stat.conflict > 0
+        || data.staging_stat.untracked > 0
+        || data.staging_stat.renamed > 0
+        || data.staging_stat.deleted > 0
     {
         let stat_str = [
             if data.staging_stat.staged > 0 {
                 Some(colored_str(
-                    &format!("🗸{}", data.staging_stat.staged),
-                    "green",
+                    &format!("{}{}", config.staged_symbol, data.staging_stat.staged),
+                    &config.staged_color,
                 ))
             } else {
                 None
             },
             if data.staging_stat.modified > 0 {
                 Some(colored_str(
-                    &format!("•{}", data.staging_stat.modified),
-                    "yellow",
+                    &format!("{}{}", config.modified_symbol, data.staging_stat.modified),
+                    &config.modified_color,
+                ))
+            } else {
+                None
+            },
+            if data.staging_stat.untracked > 0 {
+                Some(colored_str(
+                    &format!("{}{}", config.untracked_symbol, data.staging_stat.untracked),
+                    &config.untracked_color,
+                ))
+            } else {
+                None
+            },
+            if data.staging_stat.renamed > 0 {
+                Some(colored_str(
+                    &format!("{}{}", config.renamed_symbol, data.staging_stat.renamed),
+                    &config.renamed_color,
+                ))
+            } else {
+                None
+            },
+            if data.staging_stat.deleted > 0 {
+                Some(colored_str(
+                    &format!("{}{}", config.deleted_symbol, data.staging_stat.deleted),
+                    &config.deleted_color,
                 ))
             } else {
                 None
             },
             if data.staging_stat.conflict > 0 {
                 Some(colored_str(
-                    &format!("✘{}", data.staging_stat.conflict),
-                    "red",
+                    &format!("{}{}", config.conflict_symbol, data.staging_stat.conflict),
+                    &config.conflict_color,
                 ))
             } else {
                 None
@@ -271,24 +515,38 @@ fn print_prompt(data: &PromptData) {
             write!(
                 &mut stdout,
                 "{}{} -> {}",
-                bold_str(&colored_str(name, "green")),
+                bold_str(&colored_str(name, &config.branch_color)),
                 staging_info,
                 bold_str(&colored_str(
                     upstream.as_ref().map(|s| s.as_str()).unwrap_or("∅"),
-                    "red"
+                    &config.upstream_color
                 ))
             )
             .unwrap();
             if data.commit_stat.ahead > 0 || data.commit_stat.behind > 0 {
                 write!(&mut stdout, " (").unwrap();
-                if data.commit_stat.ahead > 0 {
-                    write!(&mut stdout, "{}↑", data.commit_stat.ahead).unwrap();
-                }
-                if data.commit_stat.behind > 0 {
-                    if data.commit_stat.ahead > 0 {
-                        write!(&mut stdout, ", ").unwrap();
-                    }
-                    write!(&mut stdout, "{}↓", data.commit_stat.behind).unwrap();
+                if data.commit_stat.ahead > 0 && data.commit_stat.behind > 0 {
+                    // Diverged: show a single glyph instead of separate arrows
+                    write!(
+                        &mut stdout,
+                        "{}{}{}",
+                        data.commit_stat.ahead, config.diverged_symbol, data.commit_stat.behind
+                    )
+                    .unwrap();
+                } else if data.commit_stat.ahead > 0 {
+                    write!(
+                        &mut stdout,
+                        "{}{}",
+                        data.commit_stat.ahead, config.ahead_symbol
+                    )
+                    .unwrap();
+                } else {
+                    write!(
+                        &mut stdout,
+                        "{}{}",
+                        data.commit_stat.behind, config.behind_symbol
+                    )
+                    .unwrap();
                 }
                 write!(&mut stdout, ")").unwrap();
             }
@@ -297,7 +555,7 @@ fn print_prompt(data: &PromptData) {
             write!(
                 &mut stdout,
                 "{}{}",
-                bold_str(&colored_str(name, "red")),
+                bold_str(&colored_str(name, &config.remote_branch_color)),
                 staging_info
             )
             .unwrap();
@@ -305,17 +563,18 @@ fn print_prompt(data: &PromptData) {
         HeadInfo::Tag { name } => {
             write!(
                 &mut stdout,
-                "🔖{}{}",
-                bold_str(&colored_str(name, "blue")),
+                "{}{}{}",
+                config.tag_symbol,
+                bold_str(&colored_str(name, &config.tag_color)),
                 staging_info
             )
             .unwrap();
         }
-        HeadInfo::Commit { hash } => {
+        HeadInfo::Commit { description } => {
             write!(
                 &mut stdout,
                 "Commit {}{}",
-                bold_str(&colored_str(&hash[0..=12], "blue")),
+                bold_str(&colored_str(description, &config.commit_color)),
                 staging_info
             )
             .unwrap();
@@ -324,8 +583,17 @@ fn print_prompt(data: &PromptData) {
             write!(
                 &mut stdout,
                 "{} {}",
-                bold_str(&colored_str(&name, "green")),
-                colored_str("(empty repo)", "red")
+                bold_str(&colored_str(&name, &config.branch_color)),
+                colored_str("(empty repo)", &config.empty_repo_color)
+            )
+            .unwrap();
+        }
+        HeadInfo::Unknown => {
+            write!(
+                &mut stdout,
+                "{}{}",
+                colored_str(&config.unknown_symbol, &config.unknown_color),
+                staging_info
             )
             .unwrap();
         }
@@ -333,7 +601,12 @@ fn print_prompt(data: &PromptData) {
 }
 
 fn main() {
-    let mut repo = find_repo_using_current_dir();
-    let prompt_data = prepare_prompt_data(&mut repo);
-    print_prompt(&prompt_data);
+    let config = Config::from_env();
+    // Not being in a repo (or not being able to tell) just means an empty prompt segment, not a
+    // crash
+    let Some(mut repo) = find_repo_using_current_dir() else {
+        return;
+    };
+    let prompt_data = prepare_prompt_data(&mut repo, &config);
+    print_prompt(&prompt_data, &config);
 }